@@ -1,5 +1,6 @@
-use openpond_sdk::{OpenPondSDK, OpenPondConfig, Message};
+use openpond_sdk::{EncryptionMode, OpenPondSDK, OpenPondConfig, Message};
 use std::error::Error;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -10,6 +11,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
         private_key: std::env::var("OPENPOND_PRIVATE_KEY").ok(),
         agent_name: Some("example-agent".to_string()),
         api_key: std::env::var("OPENPOND_API_KEY").ok(),
+        verify: true,
+        max_retries: None,
+        reconnect_backoff_cap: Duration::from_secs(30),
+        encryption: EncryptionMode::Preferred,
+        enable_mdns: false,
     });
 
     // Set up message handler
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use tokio::sync::Mutex;
+
+use crate::error::{OpenPondError, Result};
+
+const SERVICE_TYPE: &str = "_openpond._udp.local.";
+
+/// A peer found on the local network via mDNS, carrying enough information
+/// to deliver a message to it directly instead of through `api_url`.
+#[derive(Debug, Clone)]
+pub struct DiscoveredPeer {
+    pub agent_id: String,
+    pub name: Option<String>,
+    pub address: IpAddr,
+    pub port: u16,
+    pub last_seen: i64,
+}
+
+/// Advertises this agent on the local network and browses for peers doing
+/// the same. Gated behind `OpenPondConfig.enable_mdns` since local discovery
+/// is unwanted on restricted or privacy-sensitive networks.
+pub(crate) struct PeerDiscovery {
+    daemon: ServiceDaemon,
+    peers: Arc<Mutex<HashMap<String, DiscoveredPeer>>>,
+}
+
+impl PeerDiscovery {
+    /// Registers `agent_id`/`name` on the network at `direct_port`, and
+    /// starts browsing for other OpenPond agents, merging them into
+    /// `peers()` as they're resolved.
+    pub fn start(agent_id: &str, agent_name: Option<&str>, direct_port: u16) -> Result<Self> {
+        let daemon = ServiceDaemon::new()
+            .map_err(|e| OpenPondError::DiscoveryError(e.to_string()))?;
+
+        let instance_name = agent_id.to_string();
+        let host_name = format!("{}.local.", agent_id);
+        let mut properties = HashMap::new();
+        properties.insert("agentId".to_string(), agent_id.to_string());
+        if let Some(name) = agent_name {
+            properties.insert("name".to_string(), name.to_string());
+        }
+
+        let service_info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance_name,
+            &host_name,
+            "",
+            direct_port,
+            Some(properties),
+        )
+        .map_err(|e| OpenPondError::DiscoveryError(e.to_string()))?
+        .enable_addr_auto();
+
+        daemon
+            .register(service_info)
+            .map_err(|e| OpenPondError::DiscoveryError(e.to_string()))?;
+
+        let peers = Arc::new(Mutex::new(HashMap::new()));
+        let browse_peers = peers.clone();
+        let receiver = daemon
+            .browse(SERVICE_TYPE)
+            .map_err(|e| OpenPondError::DiscoveryError(e.to_string()))?;
+
+        tokio::spawn(async move {
+            while let Ok(event) = receiver.recv_async().await {
+                if let ServiceEvent::ServiceResolved(info) = event {
+                    let Some(peer_agent_id) = info.get_property_val_str("agentId") else {
+                        continue;
+                    };
+                    let Some(address) = info.get_addresses().iter().next().copied() else {
+                        continue;
+                    };
+                    let peer = DiscoveredPeer {
+                        agent_id: peer_agent_id.to_string(),
+                        name: info.get_property_val_str("name").map(|s| s.to_string()),
+                        address,
+                        port: info.get_port(),
+                        last_seen: chrono::Utc::now().timestamp_millis(),
+                    };
+                    browse_peers.lock().await.insert(peer.agent_id.clone(), peer);
+                }
+            }
+        });
+
+        Ok(Self { daemon, peers })
+    }
+
+    pub async fn peers(&self) -> Vec<DiscoveredPeer> {
+        self.peers.lock().await.values().cloned().collect()
+    }
+
+    pub async fn peer(&self, agent_id: &str) -> Option<DiscoveredPeer> {
+        self.peers.lock().await.get(agent_id).cloned()
+    }
+}
+
+impl Drop for PeerDiscovery {
+    fn drop(&mut self) {
+        let _ = self.daemon.shutdown();
+    }
+}
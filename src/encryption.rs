@@ -0,0 +1,155 @@
+use base64::Engine;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use x25519_dalek::{PublicKey as X25519PublicKey, StaticSecret};
+
+use crate::error::{OpenPondError, Result};
+
+/// Value of the `enc` field tagged onto `SendMessageOptions.metadata` for an
+/// encrypted envelope.
+pub(crate) const ENCRYPTION_TAG: &str = "x25519-chacha20poly1305";
+
+/// An X25519 keypair used only for the ECDH handshake behind end-to-end
+/// encrypted messages, derived from the agent's signing `private_key` so
+/// users only need to manage one secret.
+pub(crate) struct EncryptionKeypair {
+    secret: StaticSecret,
+    public: X25519PublicKey,
+}
+
+impl EncryptionKeypair {
+    pub fn derive(private_key_bytes: &[u8; 32]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(b"openpond-x25519-v1");
+        hasher.update(private_key_bytes);
+        let derived: [u8; 32] = hasher.finalize().into();
+
+        let secret = StaticSecret::from(derived);
+        let public = X25519PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.public.as_bytes())
+    }
+
+    fn shared_secret(&self, their_public_key_hex: &str) -> Result<[u8; 32]> {
+        let bytes = hex::decode(their_public_key_hex)
+            .map_err(|_| OpenPondError::EncryptionError("malformed public key".to_string()))?;
+        let bytes: [u8; 32] = bytes.try_into().map_err(|_| {
+            OpenPondError::EncryptionError("public key must be 32 bytes".to_string())
+        })?;
+        Ok(*self.secret.diffie_hellman(&X25519PublicKey::from(bytes)).as_bytes())
+    }
+
+    /// Encrypts `plaintext` for `their_public_key_hex`, returning the
+    /// base64 ciphertext (nonce prepended) and the hex nonce to tag in
+    /// metadata.
+    pub fn encrypt(&self, their_public_key_hex: &str, plaintext: &str) -> Result<(String, String)> {
+        let shared = self.shared_secret(their_public_key_hex)?;
+        let cipher = ChaCha20Poly1305::new_from_slice(&shared)
+            .map_err(|_| OpenPondError::EncryptionError("invalid shared secret".to_string()))?;
+
+        let mut nonce_bytes = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(nonce, plaintext.as_bytes())
+            .map_err(|_| OpenPondError::EncryptionError("encryption failed".to_string()))?;
+
+        let mut payload = nonce_bytes.to_vec();
+        payload.extend_from_slice(&ciphertext);
+
+        Ok((
+            base64::engine::general_purpose::STANDARD.encode(payload),
+            hex::encode(nonce_bytes),
+        ))
+    }
+
+    /// Decrypts a base64 envelope (nonce prepended) using the sender's
+    /// public key.
+    pub fn decrypt(&self, their_public_key_hex: &str, envelope_b64: &str) -> Result<String> {
+        let shared = self.shared_secret(their_public_key_hex)?;
+        let cipher = ChaCha20Poly1305::new_from_slice(&shared)
+            .map_err(|_| OpenPondError::EncryptionError("invalid shared secret".to_string()))?;
+
+        let payload = base64::engine::general_purpose::STANDARD
+            .decode(envelope_b64)
+            .map_err(|_| OpenPondError::EncryptionError("malformed ciphertext".to_string()))?;
+        if payload.len() < 12 {
+            return Err(OpenPondError::EncryptionError(
+                "ciphertext too short".to_string(),
+            ));
+        }
+        let (nonce_bytes, ciphertext) = payload.split_at(12);
+        let nonce = Nonce::from_slice(nonce_bytes);
+
+        let plaintext = cipher
+            .decrypt(nonce, ciphertext)
+            .map_err(|_| OpenPondError::EncryptionError("decryption failed".to_string()))?;
+
+        String::from_utf8(plaintext).map_err(|_| {
+            OpenPondError::EncryptionError("decrypted payload is not valid UTF-8".to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypair(seed: u8) -> EncryptionKeypair {
+        EncryptionKeypair::derive(&[seed; 32])
+    }
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips() {
+        let alice = keypair(1);
+        let bob = keypair(2);
+
+        let (envelope, nonce) = alice.encrypt(&bob.public_key_hex(), "hello bob").unwrap();
+        assert_eq!(nonce.len(), 24); // 12 bytes, hex-encoded
+
+        let plaintext = bob.decrypt(&alice.public_key_hex(), &envelope).unwrap();
+        assert_eq!(plaintext, "hello bob");
+    }
+
+    #[test]
+    fn decrypt_fails_for_wrong_recipient() {
+        let alice = keypair(1);
+        let bob = keypair(2);
+        let mallory = keypair(3);
+
+        let (envelope, _) = alice.encrypt(&bob.public_key_hex(), "hello bob").unwrap();
+
+        assert!(mallory.decrypt(&alice.public_key_hex(), &envelope).is_err());
+    }
+
+    #[test]
+    fn decrypt_fails_for_tampered_ciphertext() {
+        let alice = keypair(1);
+        let bob = keypair(2);
+
+        let (envelope, _) = alice.encrypt(&bob.public_key_hex(), "hello bob").unwrap();
+        let mut payload = base64::engine::general_purpose::STANDARD
+            .decode(envelope)
+            .unwrap();
+        let last = payload.len() - 1;
+        payload[last] ^= 0xFF;
+        let tampered = base64::engine::general_purpose::STANDARD.encode(payload);
+
+        assert!(bob.decrypt(&alice.public_key_hex(), &tampered).is_err());
+    }
+
+    #[test]
+    fn decrypt_fails_for_short_ciphertext() {
+        let bob = keypair(2);
+        let alice = keypair(1);
+
+        let too_short = base64::engine::general_purpose::STANDARD.encode([0u8; 4]);
+        assert!(bob.decrypt(&alice.public_key_hex(), &too_short).is_err());
+    }
+}
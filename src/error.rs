@@ -15,6 +15,14 @@ pub enum OpenPondError {
     HttpError(#[from] reqwest::Error),
     #[error("SSE client error")]
     SSEError,
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    #[error("Encryption error: {0}")]
+    EncryptionError(String),
+    #[error("Discovery error: {0}")]
+    DiscoveryError(String),
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
 }
 
 impl From<eventsource_client::Error> for OpenPondError {
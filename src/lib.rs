@@ -1,14 +1,39 @@
+#[cfg(feature = "mdns")]
+mod discovery;
+mod encryption;
 mod error;
+mod policy;
+mod signing;
+mod subscription;
 mod types;
 
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "mdns")]
+use rand::RngCore;
+use rand::Rng;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 use eventsource_client::{Client, SSE, ClientBuilder};
 use futures::StreamExt;
 
+use encryption::{EncryptionKeypair, ENCRYPTION_TAG};
+use signing::Keypair;
+use subscription::SubscriptionIdGenerator;
+
 pub use error::{OpenPondError, Result};
+pub use policy::{Effect, Policy};
+pub use subscription::{Subscription, SubscriptionId};
 pub use types::*;
 
+#[cfg(feature = "mdns")]
+pub use discovery::DiscoveredPeer;
+
+type MessageCallback = Arc<Mutex<Option<Box<dyn Fn(Message) + Send + Sync>>>>;
+type ErrorCallback = Arc<Mutex<Option<Box<dyn Fn(OpenPondError) + Send + Sync>>>>;
+
 /// OpenPond SDK for interacting with the P2P network.
 ///
 /// The SDK can be used in two ways:
@@ -20,8 +45,158 @@ pub use types::*;
 pub struct OpenPondSDK {
     client: reqwest::Client,
     config: OpenPondConfig,
-    message_callback: Arc<Mutex<Option<Box<dyn Fn(Message) + Send + Sync>>>>,
-    error_callback: Arc<Mutex<Option<Box<dyn Fn(OpenPondError) + Send + Sync>>>>,
+    keypair: Option<Arc<Keypair>>,
+    encryption_keypair: Option<Arc<EncryptionKeypair>>,
+    message_callback: MessageCallback,
+    error_callback: ErrorCallback,
+    cancel_token: CancellationToken,
+    subscriptions: Arc<Mutex<HashMap<SubscriptionId, Subscription>>>,
+    subscription_ids: Arc<SubscriptionIdGenerator>,
+    access_policy: Arc<Mutex<Option<Policy>>>,
+    #[cfg(feature = "mdns")]
+    discovery: Arc<Mutex<Option<Arc<discovery::PeerDiscovery>>>>,
+}
+
+/// Base delay for the first reconnect attempt; doubled on each consecutive
+/// failure up to `OpenPondConfig.reconnect_backoff_cap`.
+const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Generates an id for a message sent directly to a peer, bypassing the
+/// central API (which would normally assign one).
+#[cfg(feature = "mdns")]
+fn generate_message_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Bundles everything needed to verify, decrypt, and route one incoming
+/// message, whether it arrived over the SSE stream or a direct peer
+/// connection. Cheap to clone: the shared state is held behind `Arc`s or
+/// handles, and the rest is a few small values.
+#[derive(Clone)]
+struct DispatchContext {
+    agent_id: Option<String>,
+    verify: bool,
+    encryption_mode: EncryptionMode,
+    encryption_keypair: Option<Arc<EncryptionKeypair>>,
+    http_client: reqwest::Client,
+    api_url: String,
+    subscriptions: Arc<Mutex<HashMap<SubscriptionId, Subscription>>>,
+    message_callback: MessageCallback,
+    error_callback: ErrorCallback,
+    access_policy: Arc<Mutex<Option<Policy>>>,
+}
+
+impl DispatchContext {
+    /// Verifies the signature, decrypts the content if needed, and routes
+    /// `msg` to matching subscriptions (or the shared `on_message`
+    /// callback), dropping it if any step fails.
+    async fn dispatch(&self, mut msg: Message) {
+        if self.verify {
+            match &msg.signature {
+                Some(signature) => {
+                    if let Err(e) =
+                        signing::verify(msg.content.as_bytes(), signature, &msg.from_agent_id)
+                    {
+                        if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                            cb(e);
+                        }
+                        return;
+                    }
+                }
+                None => {
+                    if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                        cb(OpenPondError::InvalidSignature(
+                            "message is missing a signature".to_string(),
+                        ));
+                    }
+                    return;
+                }
+            }
+        }
+
+        let is_encrypted = self.encryption_mode != EncryptionMode::Disabled
+            && msg
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("enc"))
+                .and_then(|v| v.as_str())
+                == Some(ENCRYPTION_TAG);
+
+        if is_encrypted {
+            let decrypted = match &self.encryption_keypair {
+                Some(encryption_keypair) => {
+                    match OpenPondSDK::fetch_agent(&self.http_client, &self.api_url, &msg.from_agent_id).await {
+                        Ok(sender) if sender.public_key.is_some() => {
+                            encryption_keypair.decrypt(&sender.public_key.unwrap(), &msg.content)
+                        }
+                        Ok(_) => Err(OpenPondError::EncryptionError(
+                            "sender has no public key on file".to_string(),
+                        )),
+                        Err(e) => Err(e),
+                    }
+                }
+                None => Err(OpenPondError::EncryptionError(
+                    "received an encrypted message but no private key is configured".to_string(),
+                )),
+            };
+
+            match decrypted {
+                Ok(plaintext) => msg.content = plaintext,
+                Err(e) => {
+                    if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                        cb(e);
+                    }
+                    return;
+                }
+            }
+        }
+
+        if let Some(policy) = self.access_policy.lock().await.as_ref() {
+            let topic = msg
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("topic"))
+                .and_then(|v| v.as_str());
+
+            if !policy.enforce(&msg.from_agent_id, topic) {
+                if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                    cb(OpenPondError::Unauthorized(format!(
+                        "message from {} rejected by access policy",
+                        msg.from_agent_id
+                    )));
+                }
+                return;
+            }
+        }
+
+        // With no subscriptions registered, fall back to the original
+        // single-listener behavior: deliver only messages addressed to us
+        // via `on_message`.
+        let subs = self.subscriptions.lock().await;
+        if subs.is_empty() {
+            drop(subs);
+            if let Some(ref our_id) = self.agent_id {
+                if msg.to_agent_id == *our_id {
+                    if let Some(cb) = self.message_callback.lock().await.as_ref() {
+                        cb(msg);
+                    }
+                }
+            }
+        } else {
+            let matched: Vec<Subscription> = subs.values().filter(|s| s.matches(&msg)).cloned().collect();
+            drop(subs);
+
+            for sub in matched {
+                if let Some(cb) = &sub.callback {
+                    cb(msg.clone());
+                } else if let Some(cb) = self.message_callback.lock().await.as_ref() {
+                    cb(msg.clone());
+                }
+            }
+        }
+    }
 }
 
 impl OpenPondSDK {
@@ -45,14 +220,61 @@ impl OpenPondSDK {
             .build()
             .unwrap();
 
+        // Derive the agent's keypair up front so the raw private key never
+        // needs to be transmitted to the API.
+        let keypair = config
+            .private_key
+            .as_deref()
+            .and_then(|key| Keypair::from_config_key(key).ok())
+            .map(Arc::new);
+
+        // Derive a separate X25519 keypair from the same secret for the
+        // end-to-end encryption handshake, unless encryption is disabled.
+        let encryption_keypair = if config.encryption != EncryptionMode::Disabled {
+            keypair
+                .as_ref()
+                .map(|kp| Arc::new(EncryptionKeypair::derive(&kp.secret_bytes())))
+        } else {
+            None
+        };
+
         Self {
             client,
             config,
+            keypair,
+            encryption_keypair,
             message_callback: Arc::new(Mutex::new(None)),
             error_callback: Arc::new(Mutex::new(None)),
+            cancel_token: CancellationToken::new(),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            subscription_ids: Arc::new(SubscriptionIdGenerator::new()),
+            access_policy: Arc::new(Mutex::new(None)),
+            #[cfg(feature = "mdns")]
+            discovery: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Sets the access policy used to accept or reject inbound messages
+    /// before they reach subscriptions or `on_message`. Pass `Policy::default()`
+    /// to clear back to default-allow.
+    pub async fn set_access_policy(&self, policy: Policy) {
+        *self.access_policy.lock().await = Some(policy);
+    }
+
+    /// Registers a subscription so its callback (or the shared
+    /// `on_message` callback, if the subscription doesn't set one) is
+    /// invoked for every subsequent incoming message that matches it.
+    pub async fn subscribe(&self, subscription: Subscription) -> SubscriptionId {
+        let id = self.subscription_ids.next();
+        self.subscriptions.lock().await.insert(id, subscription);
+        id
+    }
+
+    /// Removes a previously registered subscription.
+    pub async fn unsubscribe(&self, id: SubscriptionId) {
+        self.subscriptions.lock().await.remove(&id);
+    }
+
     /// Set callback for receiving messages
     pub async fn on_message<F>(&self, callback: F)
     where
@@ -71,83 +293,240 @@ impl OpenPondSDK {
         *cb = Some(Box::new(callback));
     }
 
-    /// Starts the SDK and begins listening for messages using SSE
+    /// Starts the SDK and begins listening for messages using SSE.
+    ///
+    /// The stream is held open by a background task that reconnects with
+    /// exponential backoff whenever it ends or errors, resuming from the
+    /// last processed event via `Last-Event-ID`. The task exits as soon as
+    /// `stop()` is called.
     pub async fn start(&self) -> Result<()> {
         // Register the agent if not already registered
         self.register_agent().await?;
 
-        // Setup SSE client
         let url = format!("{}/messages/stream", self.config.api_url);
-        let mut builder = ClientBuilder::for_url(&url).map_err(|_| OpenPondError::SSEError)?;
-        
-        // Add required headers
+        let api_key = self.config.api_key.clone();
+        let keypair = self.keypair.clone();
+        let max_retries = self.config.max_retries;
+        let backoff_cap = self.config.reconnect_backoff_cap;
+        let cancel_token = self.cancel_token.clone();
+
+        let ctx = DispatchContext {
+            agent_id: self.keypair.as_ref().map(|kp| kp.agent_id()),
+            verify: self.config.verify,
+            encryption_mode: self.config.encryption,
+            encryption_keypair: self.encryption_keypair.clone(),
+            http_client: self.client.clone(),
+            api_url: self.config.api_url.clone(),
+            subscriptions: self.subscriptions.clone(),
+            message_callback: self.message_callback.clone(),
+            error_callback: self.error_callback.clone(),
+            access_policy: self.access_policy.clone(),
+        };
+
+        #[cfg(feature = "mdns")]
+        if self.config.enable_mdns {
+            self.start_local_discovery(ctx.clone()).await;
+        }
+
+        let error_callback = self.error_callback.clone();
+        let dispatch_ctx = ctx.clone();
+
+        // Start listening for events in a separate task
+        tokio::spawn(async move {
+            let mut last_event_id: Option<String> = None;
+            let mut delay = RECONNECT_BASE_DELAY;
+            let mut attempt: u32 = 0;
+
+            loop {
+                let builder = match Self::build_stream_client(
+                    &url,
+                    api_key.as_deref(),
+                    keypair.as_deref(),
+                    last_event_id.as_deref(),
+                ) {
+                    Ok(builder) => builder,
+                    Err(e) => {
+                        if let Some(cb) = error_callback.lock().await.as_ref() {
+                            cb(e);
+                        }
+                        return;
+                    }
+                };
+
+                let client = builder.build();
+                let mut stream = client.stream();
+
+                loop {
+                    let event = tokio::select! {
+                        _ = cancel_token.cancelled() => return,
+                        event = stream.next() => event,
+                    };
+
+                    let Some(event) = event else {
+                        // Stream ended; fall through to reconnect.
+                        break;
+                    };
+
+                    match event {
+                        Ok(SSE::Event(event)) => {
+                            attempt = 0;
+                            delay = RECONNECT_BASE_DELAY;
+                            if let Some(id) = &event.id {
+                                last_event_id = Some(id.clone());
+                            }
+
+                            if let Ok(msg) = serde_json::from_str::<Message>(&event.data) {
+                                dispatch_ctx.dispatch(msg).await;
+                            }
+                        }
+                        Err(e) => {
+                            if let Some(cb) = error_callback.lock().await.as_ref() {
+                                cb(OpenPondError::from(e));
+                            }
+                            break;
+                        }
+                        _ => {} // Ignore other event types
+                    }
+                }
+
+                // Back off on every termination, clean or not — a server
+                // that closes the stream right after each event (or right
+                // after `Last-Event-ID` is set) would otherwise be hammered
+                // in a tight reconnect loop with no delay at all.
+                attempt += 1;
+                if let Some(max) = max_retries {
+                    if attempt > max {
+                        return;
+                    }
+                }
+
+                let jitter = rand::thread_rng().gen_range(0..=delay.as_millis() as u64 / 4 + 1);
+                tokio::select! {
+                    _ = cancel_token.cancelled() => return,
+                    _ = tokio::time::sleep(delay + Duration::from_millis(jitter)) => {}
+                }
+                delay = std::cmp::min(delay * 2, backoff_cap);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Binds a listener for direct peer delivery, advertises it over mDNS,
+    /// and browses for other local agents. Errors are reported through
+    /// `on_error` rather than failing `start()`, since local discovery is a
+    /// best-effort convenience on top of the central API.
+    #[cfg(feature = "mdns")]
+    async fn start_local_discovery(&self, ctx: DispatchContext) {
+        let listener = match tokio::net::TcpListener::bind("0.0.0.0:0").await {
+            Ok(listener) => listener,
+            Err(e) => {
+                if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                    cb(OpenPondError::DiscoveryError(e.to_string()));
+                }
+                return;
+            }
+        };
+
+        let port = match listener.local_addr() {
+            Ok(addr) => addr.port(),
+            Err(e) => {
+                if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                    cb(OpenPondError::DiscoveryError(e.to_string()));
+                }
+                return;
+            }
+        };
+
+        let cancel_token = self.cancel_token.clone();
+        tokio::spawn(async move {
+            loop {
+                let accepted = tokio::select! {
+                    _ = cancel_token.cancelled() => return,
+                    accepted = listener.accept() => accepted,
+                };
+
+                let Ok((mut socket, _)) = accepted else {
+                    continue;
+                };
+
+                let ctx = ctx.clone();
+                tokio::spawn(async move {
+                    use tokio::io::AsyncReadExt;
+
+                    let mut buf = String::new();
+                    if socket.read_to_string(&mut buf).await.is_ok() {
+                        if let Ok(msg) = serde_json::from_str::<Message>(&buf) {
+                            ctx.dispatch(msg).await;
+                        }
+                    }
+                });
+            }
+        });
+
+        let agent_id = match &self.keypair {
+            Some(keypair) => keypair.agent_id(),
+            None => return,
+        };
+
+        match discovery::PeerDiscovery::start(&agent_id, self.config.agent_name.as_deref(), port) {
+            Ok(peer_discovery) => {
+                *self.discovery.lock().await = Some(Arc::new(peer_discovery));
+            }
+            Err(e) => {
+                if let Some(cb) = self.error_callback.lock().await.as_ref() {
+                    cb(e);
+                }
+            }
+        }
+    }
+
+    /// Builds a fresh SSE `ClientBuilder` for (re)connecting to the message
+    /// stream, attaching auth headers and `Last-Event-ID` for resumption.
+    fn build_stream_client(
+        url: &str,
+        api_key: Option<&str>,
+        keypair: Option<&Keypair>,
+        last_event_id: Option<&str>,
+    ) -> Result<ClientBuilder> {
+        let mut builder = ClientBuilder::for_url(url).map_err(|_| OpenPondError::SSEError)?;
+
         builder = builder
             .header("Accept", "text/event-stream")
             .map_err(|_| OpenPondError::SSEError)?;
 
-        // Add authentication headers
-        if let Some(private_key) = &self.config.private_key {
+        if let Some(keypair) = keypair {
             let timestamp = chrono::Utc::now().timestamp_millis().to_string();
             let message = format!("Authenticate to OpenPond API at timestamp {}", timestamp);
-            
+            let signature = keypair.sign(message.as_bytes())?;
+
             builder = builder
-                .header("X-Agent-Id", private_key)
+                .header("X-Agent-Id", &keypair.agent_id())
                 .map_err(|_| OpenPondError::SSEError)?
                 .header("X-Timestamp", &timestamp)
+                .map_err(|_| OpenPondError::SSEError)?
+                .header("X-Signature", &signature)
                 .map_err(|_| OpenPondError::SSEError)?;
-                
-            // TODO: Add signature header once we implement signing
-            // .header("X-Signature", signature)
         }
-        
-        if let Some(api_key) = &self.config.api_key {
+
+        if let Some(api_key) = api_key {
             builder = builder
                 .header("X-API-Key", api_key)
                 .map_err(|_| OpenPondError::SSEError)?;
         }
 
-        // Clone the callbacks for the async task
-        let message_callback = self.message_callback.clone();
-        let error_callback = self.error_callback.clone();
-        let agent_id = self.config.private_key.clone();
-
-        // Start listening for events in a separate task
-        tokio::spawn(async move {
-            let client = builder.build();
-            let mut stream = client.stream();
-
-            while let Some(event) = stream.next().await {
-                match event {
-                    Ok(SSE::Event(event)) => {
-                        if let Ok(msg) = serde_json::from_str::<Message>(&event.data) {
-                            // Only process messages intended for us
-                            if let Some(ref our_id) = agent_id {
-                                if msg.to_agent_id == *our_id {
-                                    if let Some(cb) = message_callback.lock().await.as_ref() {
-                                        cb(msg);
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        if let Some(cb) = error_callback.lock().await.as_ref() {
-                            cb(OpenPondError::from(e));
-                        }
-                        // Wait a bit before reconnecting on error
-                        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                    }
-                    _ => {} // Ignore other event types
-                }
-            }
-        });
+        if let Some(last_event_id) = last_event_id {
+            builder = builder
+                .header("Last-Event-ID", last_event_id)
+                .map_err(|_| OpenPondError::SSEError)?;
+        }
 
-        Ok(())
+        Ok(builder)
     }
 
     /// Stops the SDK and cleans up resources
     pub async fn stop(&self) -> Result<()> {
-        // Nothing to clean up since the stream will be dropped when the task ends
+        self.cancel_token.cancel();
         Ok(())
     }
 
@@ -158,14 +537,69 @@ impl OpenPondSDK {
         content: &str,
         options: Option<SendMessageOptions>,
     ) -> Result<String> {
+        let mut options = options;
+
+        let outgoing_content = if self.config.encryption != EncryptionMode::Disabled {
+            match (&self.encryption_keypair, self.get_agent(to_agent_id).await.ok()) {
+                (Some(encryption_keypair), Some(recipient)) if recipient.public_key.is_some() => {
+                    let recipient_public_key = recipient.public_key.unwrap();
+                    let (envelope, nonce) = encryption_keypair.encrypt(&recipient_public_key, content)?;
+
+                    let mut metadata = options
+                        .as_ref()
+                        .and_then(|o| o.metadata.clone())
+                        .unwrap_or(serde_json::json!({}));
+                    metadata["enc"] = serde_json::Value::String(ENCRYPTION_TAG.to_string());
+                    metadata["nonce"] = serde_json::Value::String(nonce);
+
+                    options = Some(SendMessageOptions {
+                        reply_to: options.and_then(|o| o.reply_to),
+                        metadata: Some(metadata),
+                    });
+
+                    envelope
+                }
+                _ if self.config.encryption == EncryptionMode::Required => {
+                    return Err(OpenPondError::EncryptionError(
+                        "recipient has no public key on file".to_string(),
+                    ));
+                }
+                _ => content.to_string(),
+            }
+        } else {
+            content.to_string()
+        };
+
+        let signature = self
+            .keypair
+            .as_ref()
+            .map(|keypair| keypair.sign(outgoing_content.as_bytes()))
+            .transpose()?;
+
+        #[cfg(feature = "mdns")]
+        if self.config.enable_mdns {
+            if let Some(message_id) = self
+                .try_send_direct(to_agent_id, &outgoing_content, &signature, &options)
+                .await
+            {
+                return Ok(message_id);
+            }
+        }
+
+        let mut body = serde_json::json!({
+            "toAgentId": to_agent_id,
+            "content": outgoing_content,
+            "options": options,
+        });
+
+        if let (Some(keypair), Some(signature)) = (&self.keypair, &signature) {
+            body["fromAgentId"] = serde_json::Value::String(keypair.agent_id());
+            body["signature"] = serde_json::Value::String(signature.clone());
+        }
+
         let response = self.client
-            .post(&format!("{}/messages", self.config.api_url))
-            .json(&serde_json::json!({
-                "toAgentId": to_agent_id,
-                "content": content,
-                "privateKey": self.config.private_key,
-                "options": options,
-            }))
+            .post(format!("{}/messages", self.config.api_url))
+            .json(&body)
             .send()
             .await?;
 
@@ -180,10 +614,57 @@ impl OpenPondSDK {
         Ok(data["messageId"].as_str().unwrap_or_default().to_string())
     }
 
+    /// Attempts to deliver a message straight to a peer discovered on the
+    /// local network, skipping `api_url` entirely. Returns the generated
+    /// message id on success, or `None` if no local route is known or
+    /// delivery fails, so the caller can fall back to the API.
+    #[cfg(feature = "mdns")]
+    async fn try_send_direct(
+        &self,
+        to_agent_id: &str,
+        content: &str,
+        signature: &Option<String>,
+        options: &Option<SendMessageOptions>,
+    ) -> Option<String> {
+        use tokio::io::AsyncWriteExt;
+
+        let discovery = self.discovery.lock().await.clone()?;
+        let peer = discovery.peer(to_agent_id).await?;
+
+        let message = Message {
+            id: generate_message_id(),
+            from_agent_id: self.keypair.as_ref().map(|kp| kp.agent_id()).unwrap_or_default(),
+            to_agent_id: to_agent_id.to_string(),
+            content: content.to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            signature: signature.clone(),
+            sender_public_key: self.keypair.as_ref().map(|kp| kp.agent_id()),
+            metadata: options.as_ref().and_then(|o| o.metadata.clone()),
+        };
+
+        let payload = serde_json::to_vec(&message).ok()?;
+        let addr = std::net::SocketAddr::new(peer.address, peer.port);
+
+        let mut stream = tokio::time::timeout(Duration::from_secs(2), tokio::net::TcpStream::connect(addr))
+            .await
+            .ok()?
+            .ok()?;
+        stream.write_all(&payload).await.ok()?;
+        stream.shutdown().await.ok()?;
+
+        Some(message.id)
+    }
+
     /// Gets information about an agent
     pub async fn get_agent(&self, agent_id: &str) -> Result<Agent> {
-        let response = self.client
-            .get(&format!("{}/agents/{}", self.config.api_url, agent_id))
+        Self::fetch_agent(&self.client, &self.config.api_url, agent_id).await
+    }
+
+    /// Fetches an agent without requiring `&self`, so the SSE stream task
+    /// can look up a sender's public key to decrypt a message.
+    async fn fetch_agent(client: &reqwest::Client, api_url: &str, agent_id: &str) -> Result<Agent> {
+        let response = client
+            .get(format!("{}/agents/{}", api_url, agent_id))
             .send()
             .await?;
 
@@ -200,7 +681,7 @@ impl OpenPondSDK {
     /// Lists all registered agents
     pub async fn list_agents(&self) -> Result<Vec<Agent>> {
         let response = self.client
-            .get(&format!("{}/agents", self.config.api_url))
+            .get(format!("{}/agents", self.config.api_url))
             .send()
             .await?;
 
@@ -212,16 +693,70 @@ impl OpenPondSDK {
         }
 
         let data: serde_json::Value = response.json().await?;
-        Ok(serde_json::from_value(data["agents"].clone())?)
+        let agents: Vec<Agent> = serde_json::from_value(data["agents"].clone())?;
+
+        #[cfg(feature = "mdns")]
+        let agents = {
+            let mut by_id: HashMap<String, Agent> =
+                agents.into_iter().map(|agent| (agent.id.clone(), agent)).collect();
+
+            if let Some(discovery) = self.discovery.lock().await.clone() {
+                for peer in discovery.peers().await {
+                    match by_id.get_mut(&peer.agent_id) {
+                        // Already known from the API: prefer the
+                        // locally-seen `last_seen` and name, but keep the
+                        // API-published `public_key` — mDNS never carries
+                        // one, so overwriting it would break encryption to
+                        // agents that are also on the LAN.
+                        Some(existing) => {
+                            existing.last_seen = Some(peer.last_seen);
+                            if peer.name.is_some() {
+                                existing.name = peer.name;
+                            }
+                        }
+                        None => {
+                            by_id.insert(
+                                peer.agent_id.clone(),
+                                Agent {
+                                    id: peer.agent_id,
+                                    name: peer.name,
+                                    last_seen: Some(peer.last_seen),
+                                    public_key: None,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+
+            by_id.into_values().collect()
+        };
+
+        Ok(agents)
     }
 
     async fn register_agent(&self) -> Result<()> {
+        let mut body = serde_json::json!({
+            "name": self.config.agent_name,
+        });
+
+        if let Some(keypair) = &self.keypair {
+            let timestamp = chrono::Utc::now().timestamp_millis().to_string();
+            let message = format!("Authenticate to OpenPond API at timestamp {}", timestamp);
+            let signature = keypair.sign(message.as_bytes())?;
+
+            body["agentId"] = serde_json::Value::String(keypair.agent_id());
+            body["timestamp"] = serde_json::Value::String(timestamp);
+            body["signature"] = serde_json::Value::String(signature);
+        }
+
+        if let Some(encryption_keypair) = &self.encryption_keypair {
+            body["publicKey"] = serde_json::Value::String(encryption_keypair.public_key_hex());
+        }
+
         let response = self.client
-            .post(&format!("{}/agents/register", self.config.api_url))
-            .json(&serde_json::json!({
-                "privateKey": self.config.private_key,
-                "name": self.config.agent_name,
-            }))
+            .post(format!("{}/agents/register", self.config.api_url))
+            .json(&body)
             .send()
             .await?;
 
@@ -0,0 +1,155 @@
+/// Whether a matching rule permits or rejects a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+/// A single allow/deny rule. `subject` matches a `from_agent_id` either
+/// exactly or, if it ends in `*`, as a prefix. `action` matches the
+/// `"topic"` field of `SendMessageOptions.metadata`, or any topic if
+/// `None`.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    subject: String,
+    action: Option<String>,
+    effect: Effect,
+}
+
+impl Rule {
+    fn matches(&self, from_agent_id: &str, topic: Option<&str>) -> bool {
+        let subject_matches = match self.subject.strip_suffix('*') {
+            Some(prefix) => from_agent_id.starts_with(prefix),
+            None => self.subject == from_agent_id,
+        };
+
+        let action_matches = match &self.action {
+            Some(action) => Some(action.as_str()) == topic,
+            None => true,
+        };
+
+        subject_matches && action_matches
+    }
+}
+
+/// An allow/deny policy for inbound messages, evaluated in the stream task
+/// before `on_message` fires. Rules are deny-overrides: if any matching
+/// rule denies, the message is rejected even if another rule allows it.
+/// When no rule matches, `default_effect` decides.
+#[derive(Debug, Clone)]
+pub struct Policy {
+    rules: Vec<Rule>,
+    default_effect: Effect,
+}
+
+impl Policy {
+    pub fn new(default_effect: Effect) -> Self {
+        Self {
+            rules: Vec::new(),
+            default_effect,
+        }
+    }
+
+    /// Allows messages from `subject` (exact agent id, or a `prefix*`
+    /// glob), optionally restricted to a single `topic`.
+    pub fn allow(mut self, subject: impl Into<String>, topic: Option<&str>) -> Self {
+        self.rules.push(Rule {
+            subject: subject.into(),
+            action: topic.map(String::from),
+            effect: Effect::Allow,
+        });
+        self
+    }
+
+    /// Denies messages from `subject` (exact agent id, or a `prefix*`
+    /// glob), optionally restricted to a single `topic`.
+    pub fn deny(mut self, subject: impl Into<String>, topic: Option<&str>) -> Self {
+        self.rules.push(Rule {
+            subject: subject.into(),
+            action: topic.map(String::from),
+            effect: Effect::Deny,
+        });
+        self
+    }
+
+    /// Returns whether a message from `from_agent_id` about `topic` may
+    /// reach `on_message`.
+    pub fn enforce(&self, from_agent_id: &str, topic: Option<&str>) -> bool {
+        let mut matched_allow = false;
+
+        for rule in &self.rules {
+            if rule.matches(from_agent_id, topic) {
+                match rule.effect {
+                    Effect::Deny => return false,
+                    Effect::Allow => matched_allow = true,
+                }
+            }
+        }
+
+        matched_allow || self.default_effect == Effect::Allow
+    }
+}
+
+impl Default for Policy {
+    /// No rules, default-allow: behaves like no policy was set at all.
+    fn default() -> Self {
+        Self::new(Effect::Allow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_allow_with_no_rules() {
+        let policy = Policy::new(Effect::Allow);
+        assert!(policy.enforce("alice", None));
+    }
+
+    #[test]
+    fn default_deny_with_no_rules() {
+        let policy = Policy::new(Effect::Deny);
+        assert!(!policy.enforce("alice", None));
+    }
+
+    #[test]
+    fn explicit_allow_overrides_default_deny() {
+        let policy = Policy::new(Effect::Deny).allow("alice", None);
+        assert!(policy.enforce("alice", None));
+        assert!(!policy.enforce("bob", None));
+    }
+
+    #[test]
+    fn deny_overrides_a_matching_allow() {
+        let policy = Policy::new(Effect::Allow).allow("alice", None).deny("alice", None);
+        assert!(!policy.enforce("alice", None));
+    }
+
+    #[test]
+    fn deny_overrides_regardless_of_rule_order() {
+        let policy = Policy::new(Effect::Allow).deny("alice", None).allow("alice", None);
+        assert!(!policy.enforce("alice", None));
+    }
+
+    #[test]
+    fn prefix_glob_matches_subject() {
+        let policy = Policy::new(Effect::Deny).allow("trusted-*", None);
+        assert!(policy.enforce("trusted-bot", None));
+        assert!(!policy.enforce("untrusted-bot", None));
+    }
+
+    #[test]
+    fn action_restricts_to_matching_topic() {
+        let policy = Policy::new(Effect::Allow).deny("alice", Some("spam"));
+        assert!(policy.enforce("alice", Some("chat")));
+        assert!(!policy.enforce("alice", Some("spam")));
+    }
+
+    #[test]
+    fn rule_without_action_matches_any_topic() {
+        let policy = Policy::new(Effect::Allow).deny("alice", None);
+        assert!(!policy.enforce("alice", Some("chat")));
+        assert!(!policy.enforce("alice", None));
+    }
+}
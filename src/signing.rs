@@ -0,0 +1,95 @@
+use secp256k1::{Message as SecpMessage, PublicKey, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256};
+
+use crate::error::{OpenPondError, Result};
+
+/// A keypair derived from an `OpenPondConfig.private_key`, used to sign
+/// authentication challenges and outgoing messages.
+///
+/// `private_key` is accepted as 32 bytes of hex or base64; it is used to
+/// derive the keypair and is never transmitted to the API.
+pub(crate) struct Keypair {
+    secret_key: SecretKey,
+    pub public_key: PublicKey,
+}
+
+impl Keypair {
+    pub fn from_config_key(private_key: &str) -> Result<Self> {
+        let bytes = decode_private_key(private_key)?;
+        let secret_key = SecretKey::from_slice(&bytes)
+            .map_err(|_| OpenPondError::InvalidSignature("malformed private key".to_string()))?;
+        let secp = Secp256k1::signing_only();
+        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+        Ok(Self {
+            secret_key,
+            public_key,
+        })
+    }
+
+    /// The agent id derived from the public key, as used to identify the
+    /// agent to the API instead of the raw private key.
+    pub fn agent_id(&self) -> String {
+        hex::encode(self.public_key.serialize())
+    }
+
+    /// The raw 32-byte secret, used to derive other keypairs (e.g. the
+    /// X25519 key used for end-to-end encryption) from the same source of
+    /// truth instead of asking the user to manage multiple secrets.
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.secret_key.secret_bytes()
+    }
+
+    /// Signs `message`, returning a hex-encoded compact signature suitable
+    /// for the `X-Signature` header.
+    pub fn sign(&self, message: &[u8]) -> Result<String> {
+        let secp = Secp256k1::signing_only();
+        let digest = Sha256::digest(message);
+        let secp_message = SecpMessage::from_digest_slice(&digest)
+            .map_err(|_| OpenPondError::InvalidSignature("bad digest".to_string()))?;
+        let signature = secp.sign_ecdsa(&secp_message, &self.secret_key);
+        Ok(hex::encode(signature.serialize_compact()))
+    }
+}
+
+/// Verifies that `signature` (hex-encoded compact ECDSA) over `message` was
+/// produced by `from_agent_id` (itself the hex-encoded compressed
+/// secp256k1 public key — see `Keypair::agent_id`). Verifying against
+/// `from_agent_id` directly, rather than a separately attached public key,
+/// rules out a message that signs with an attacker's key while claiming a
+/// victim's `from_agent_id`.
+pub(crate) fn verify(message: &[u8], signature_hex: &str, from_agent_id: &str) -> Result<()> {
+    let secp = Secp256k1::verification_only();
+
+    let public_key_bytes = hex::decode(from_agent_id)
+        .map_err(|_| OpenPondError::InvalidSignature("malformed public key".to_string()))?;
+    let public_key = PublicKey::from_slice(&public_key_bytes)
+        .map_err(|_| OpenPondError::InvalidSignature("malformed public key".to_string()))?;
+
+    let signature_bytes = hex::decode(signature_hex)
+        .map_err(|_| OpenPondError::InvalidSignature("malformed signature".to_string()))?;
+    let signature = secp256k1::ecdsa::Signature::from_compact(&signature_bytes)
+        .map_err(|_| OpenPondError::InvalidSignature("malformed signature".to_string()))?;
+
+    let digest = Sha256::digest(message);
+    let secp_message = SecpMessage::from_digest_slice(&digest)
+        .map_err(|_| OpenPondError::InvalidSignature("bad digest".to_string()))?;
+
+    secp.verify_ecdsa(&secp_message, &signature, &public_key)
+        .map_err(|_| OpenPondError::InvalidSignature("signature does not match".to_string()))
+}
+
+fn decode_private_key(private_key: &str) -> Result<[u8; 32]> {
+    use base64::Engine;
+
+    let bytes = if let Ok(bytes) = hex::decode(private_key.trim_start_matches("0x")) {
+        bytes
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(private_key)
+            .map_err(|_| OpenPondError::InvalidSignature("private key is not hex or base64".to_string()))?
+    };
+
+    bytes
+        .try_into()
+        .map_err(|_| OpenPondError::InvalidSignature("private key must be 32 bytes".to_string()))
+}
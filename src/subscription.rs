@@ -0,0 +1,185 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::types::Message;
+
+/// Opaque handle returned by `OpenPondSDK::subscribe`, used to later
+/// `unsubscribe` the listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+pub(crate) struct SubscriptionIdGenerator(AtomicU64);
+
+impl SubscriptionIdGenerator {
+    pub fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    pub fn next(&self) -> SubscriptionId {
+        SubscriptionId(self.0.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A relay-style filter describing which incoming messages a listener is
+/// interested in. Predicates are conjunctive: a message must satisfy every
+/// predicate that is `Some` to match.
+#[derive(Clone, Default)]
+pub struct Subscription {
+    pub from_agent_ids: Option<HashSet<String>>,
+    pub to_agent_ids: Option<HashSet<String>>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub metadata: Option<(String, serde_json::Value)>,
+    pub(crate) callback: Option<Arc<dyn Fn(Message) + Send + Sync>>,
+}
+
+impl Subscription {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_agent_ids(mut self, ids: impl IntoIterator<Item = String>) -> Self {
+        self.from_agent_ids = Some(ids.into_iter().collect());
+        self
+    }
+
+    pub fn to_agent_ids(mut self, ids: impl IntoIterator<Item = String>) -> Self {
+        self.to_agent_ids = Some(ids.into_iter().collect());
+        self
+    }
+
+    pub fn since(mut self, since: i64) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    pub fn until(mut self, until: i64) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn metadata(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.metadata = Some((key.into(), value));
+        self
+    }
+
+    /// Sets a callback invoked only for messages matching this subscription.
+    /// When unset, matching messages fall through to the SDK's shared
+    /// `on_message` callback.
+    pub fn on_message<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(Message) + Send + Sync + 'static,
+    {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    pub(crate) fn matches(&self, msg: &Message) -> bool {
+        if let Some(from_agent_ids) = &self.from_agent_ids {
+            if !from_agent_ids.contains(&msg.from_agent_id) {
+                return false;
+            }
+        }
+
+        if let Some(to_agent_ids) = &self.to_agent_ids {
+            if !to_agent_ids.contains(&msg.to_agent_id) {
+                return false;
+            }
+        }
+
+        if let Some(since) = self.since {
+            if msg.timestamp < since {
+                return false;
+            }
+        }
+
+        if let Some(until) = self.until {
+            if msg.timestamp > until {
+                return false;
+            }
+        }
+
+        if let Some((key, value)) = &self.metadata {
+            let matched = msg
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get(key))
+                .map(|found| found == value)
+                .unwrap_or(false);
+            if !matched {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(from: &str, to: &str, timestamp: i64, metadata: Option<serde_json::Value>) -> Message {
+        Message {
+            id: "msg-1".to_string(),
+            from_agent_id: from.to_string(),
+            to_agent_id: to.to_string(),
+            content: "hello".to_string(),
+            timestamp,
+            signature: None,
+            sender_public_key: None,
+            metadata,
+        }
+    }
+
+    #[test]
+    fn empty_subscription_matches_everything() {
+        let sub = Subscription::new();
+        assert!(sub.matches(&message("alice", "bob", 100, None)));
+    }
+
+    #[test]
+    fn from_agent_ids_excludes_non_members() {
+        let sub = Subscription::new().from_agent_ids(["alice".to_string()]);
+        assert!(sub.matches(&message("alice", "bob", 100, None)));
+        assert!(!sub.matches(&message("carol", "bob", 100, None)));
+    }
+
+    #[test]
+    fn since_and_until_bound_the_timestamp() {
+        let sub = Subscription::new().since(100).until(200);
+        assert!(!sub.matches(&message("alice", "bob", 99, None)));
+        assert!(sub.matches(&message("alice", "bob", 150, None)));
+        assert!(!sub.matches(&message("alice", "bob", 201, None)));
+    }
+
+    #[test]
+    fn metadata_predicate_requires_matching_value() {
+        let sub = Subscription::new().metadata("topic", serde_json::json!("chat"));
+        assert!(sub.matches(&message(
+            "alice",
+            "bob",
+            100,
+            Some(serde_json::json!({"topic": "chat"}))
+        )));
+        assert!(!sub.matches(&message(
+            "alice",
+            "bob",
+            100,
+            Some(serde_json::json!({"topic": "news"}))
+        )));
+        assert!(!sub.matches(&message("alice", "bob", 100, None)));
+    }
+
+    #[test]
+    fn predicates_are_conjunctive() {
+        let sub = Subscription::new()
+            .from_agent_ids(["alice".to_string()])
+            .to_agent_ids(["bob".to_string()]);
+
+        // Matches `from` but not `to`: the whole subscription must reject it.
+        assert!(!sub.matches(&message("alice", "carol", 100, None)));
+        assert!(sub.matches(&message("alice", "bob", 100, None)));
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -6,6 +8,53 @@ pub struct OpenPondConfig {
     pub private_key: Option<String>,
     pub agent_name: Option<String>,
     pub api_key: Option<String>,
+    /// Verify the signature attached to every incoming message against its
+    /// `from_agent_id` (the sender's public key) before it reaches
+    /// `on_message`; unsigned messages are dropped. Requires the sender to
+    /// have signed with a `private_key`-derived keypair.
+    pub verify: bool,
+    /// Maximum number of consecutive reconnection attempts before the SSE
+    /// loop gives up and stops. `None` retries forever.
+    pub max_retries: Option<u32>,
+    /// Upper bound for the exponential reconnect backoff delay.
+    pub reconnect_backoff_cap: Duration,
+    /// Whether messages should be end-to-end encrypted via an X25519/
+    /// ChaCha20-Poly1305 handshake with the recipient.
+    pub encryption: EncryptionMode,
+    /// Advertise this agent over mDNS and discover LAN peers, allowing
+    /// `send_message` to deliver straight to a peer instead of through
+    /// `api_url`. Off by default since some networks don't allow, or users
+    /// don't want, local broadcast discovery.
+    pub enable_mdns: bool,
+}
+
+impl Default for OpenPondConfig {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.openpond.com".to_string(),
+            private_key: None,
+            agent_name: None,
+            api_key: None,
+            verify: true,
+            max_retries: None,
+            reconnect_backoff_cap: Duration::from_secs(30),
+            encryption: EncryptionMode::Preferred,
+            enable_mdns: false,
+        }
+    }
+}
+
+/// Controls whether `send_message` end-to-end encrypts outgoing content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EncryptionMode {
+    /// Never encrypt; always send plaintext content.
+    Disabled,
+    /// Encrypt when the recipient has published a public key, otherwise
+    /// fall back to plaintext.
+    Preferred,
+    /// Encrypt or fail; `send_message` returns an error if the recipient
+    /// has no public key on file.
+    Required,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +64,14 @@ pub struct Message {
     pub to_agent_id: String,
     pub content: String,
     pub timestamp: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Informational only: `from_agent_id` is itself the hex-encoded
+    /// public key, so verification never depends on this field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_public_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +79,11 @@ pub struct Agent {
     pub id: String,
     pub name: Option<String>,
     pub last_seen: Option<i64>,
+    /// Hex-encoded X25519 public key, published so other agents can
+    /// encrypt messages to this agent. `None` if the agent hasn't
+    /// published one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]